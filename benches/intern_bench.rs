@@ -0,0 +1,44 @@
+//! Compares the default `FxBuildHasher` against `std`'s `RandomState`
+//! (SipHash) for the hot path of interning: hashing and looking up a string
+//! that is already present in the pool.
+//!
+//! Run with `cargo +nightly bench`.
+#![feature(test)]
+
+extern crate test;
+
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+
+use stagiaire::{FxBuildHasher, Interner, Symbol};
+use test::Bencher;
+
+const WORDS: &[&str] = &[
+    "as", "break", "const", "continue", "crate", "else", "enum", "extern", "false", "fn", "for",
+    "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return",
+    "self", "Self", "static", "struct", "super", "trait", "true", "type", "unsafe", "use",
+    "where", "while",
+];
+
+// Returns (and thus keeps alive) the interned symbols, so callers don't
+// immediately drop-and-free every entry they just looked up -- otherwise
+// each `intern` call becomes an insert-then-free cycle instead of the
+// repeated-lookup-of-an-already-present-value hot path this is meant to
+// measure.
+fn intern_all_words<S: BuildHasher + Default>(interner: &Interner<S>) -> Vec<Symbol<'_, S>> {
+    WORDS.iter().map(|word| test::black_box(interner.intern(word))).collect()
+}
+
+#[bench]
+fn intern_repeated_lookups_fx(b: &mut Bencher) {
+    let interner: Interner<FxBuildHasher> = Interner::new();
+    let _keep = intern_all_words(&interner);
+    b.iter(|| test::black_box(intern_all_words(&interner)));
+}
+
+#[bench]
+fn intern_repeated_lookups_siphash(b: &mut Bencher) {
+    let interner: Interner<RandomState> = Interner::new();
+    let _keep = intern_all_words(&interner);
+    b.iter(|| test::black_box(intern_all_words(&interner)));
+}
@@ -1,10 +1,10 @@
 //! A string interner.
 //!
 //! A string interner stores a pool of immutable strings keeping a single copy
-//! of each string value.  A [`Symbol`] is a wrapper over a pointer to
-//! one of these unique string values.  Symbols can be compared quickly (pointer
-//! rather than string comparisons) and are cheaper to store than strings when
-//! several occurrences of a given string exist.
+//! of each string value.  A [`Symbol`] is a small, reference-counted index
+//! into this pool.  Symbols can be compared quickly (integer rather than
+//! string comparisons) and are cheaper to store than strings when several
+//! occurrences of a given string exist.
 //!
 //! # Examples
 //!
@@ -19,11 +19,8 @@
 //! let another_foo = Symbol::new("foo");
 //! assert_eq!(a_foo, another_foo);
 //!
-//! // Both symbols point to the same underlying value.
-//! assert_eq!(a_foo.as_str().as_ptr(), another_foo.as_str().as_ptr());
-//!
-//! // A symbol has the same size as a reference.
-//! assert_eq!(std::mem::size_of::<Symbol>(), std::mem::size_of::<&str>());
+//! // Both symbols share the same index.
+//! assert_eq!(a_foo.index(), another_foo.index());
 //!
 //! // Symbols pointing to different values are not equal.
 //! let a_bar = Symbol::new("bar");
@@ -32,9 +29,16 @@
 //!
 //! # Lifetime
 //!
-//! The interner is a process-wide singleton not exposed programmatically and
-//! string values stored there persist until the owning process terminates and
-//! have therefore a `'static` lifetime.
+//! [`Symbol::new`] is a convenience built on top of a lazily-initialized
+//! process-wide [`Interner`]; its symbols have `'static` lifetime. Code that
+//! would rather scope interning to, say, a single compilation unit can
+//! instead create its own [`Interner`], whose symbols are tied to the
+//! interner and cannot outlive it.
+//!
+//! Either way, a [`Symbol`] is reference-counted: cloning one increments a
+//! count, dropping one decrements it, and once the last clone of a given
+//! value is dropped its storage is reclaimed instead of leaking for the
+//! remainder of the interner's lifetime.
 //!
 //! # Thread-safety
 //!
@@ -42,105 +46,625 @@
 //!
 //! [`Symbol`]: struct.Symbol.html
 
-use std::collections::HashSet;
-use std::hash::{Hash, Hasher};
+use std::alloc::{self, Layout};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::hash::{BuildHasher, BuildHasherDefault, Hash, Hasher};
+use std::num::NonZeroU32;
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Mutex;
 
 use lazy_static::lazy_static;
 
-/// Wrapper over a reference to an interned string.
+// Re-exported (under a name distinct from the `lazy_static!` macro import
+// above, to avoid a self-referential `lazy_static` import) so `symbols!`,
+// invoked from a downstream crate, can expand to
+// `$crate::__lazy_static::lazy_static! { .. }` without that crate needing
+// its own `lazy_static` dependency.
+#[doc(hidden)]
+pub use lazy_static as __lazy_static;
+
+/// The `FxHash` algorithm used by `rustc` and the `rustc-hash`/`fxhash`
+/// crates: noticeably faster than `std`'s default SipHash at the cost of
+/// resistance to adversarially-chosen keys, which [`Interner`] has no need
+/// for since interning is an internal, not attacker-facing, data structure.
+///
+/// Used as [`Interner`]'s default hasher, since interning is dominated by
+/// hashing the looked-up string on every call.
+#[derive(Default)]
+pub struct FxHasher {
+    hash: u64,
+}
+
+const FX_SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+impl FxHasher {
+    #[inline]
+    fn write_u64(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(FX_SEED);
+    }
+}
+
+impl Hasher for FxHasher {
+    fn write(&mut self, mut bytes: &[u8]) {
+        while bytes.len() >= 8 {
+            let (chunk, rest) = bytes.split_at(8);
+            self.write_u64(u64::from_ne_bytes(chunk.try_into().unwrap()));
+            bytes = rest;
+        }
+        if bytes.len() >= 4 {
+            let (chunk, rest) = bytes.split_at(4);
+            self.write_u64(u32::from_ne_bytes(chunk.try_into().unwrap()) as u64);
+            bytes = rest;
+        }
+        for &byte in bytes {
+            self.write_u64(byte as u64);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+/// The [`BuildHasher`] for [`FxHasher`], and [`Interner`]'s default hasher.
+pub type FxBuildHasher = BuildHasherDefault<FxHasher>;
+
+/// A pool of interned strings.
+///
+/// Unlike the hidden process-wide pool backing [`Symbol::new`], an
+/// `Interner` is an ordinary value: it can be created, passed around, and
+/// dropped. Symbols produced by [`Interner::intern`] cannot outlive the
+/// interner that created them (see [`Symbol`]'s `'i` parameter); within that
+/// bound, each interned value's storage is additionally reclaimed as soon as
+/// its last `Symbol` is dropped, rather than living until the whole
+/// interner is dropped.
+///
+/// Internally, each interned string is assigned a small, contiguous index;
+/// [`Symbol`] stores that index together with a pointer to the value's
+/// storage and a reference back to the interner, so it is larger than the
+/// index alone. [`Interner::resolve`] maps the index back to the string.
+///
+/// `S` is the [`BuildHasher`] used for the interner's dedup table; it
+/// defaults to [`FxBuildHasher`], which is faster than `std`'s default
+/// SipHash for the short, hot-path lookups interning does. Pass
+/// `std::collections::hash_map::RandomState` instead if resistance to
+/// adversarially-chosen inputs matters for your use case.
+pub struct Interner<S: BuildHasher = FxBuildHasher> {
+    tables: Mutex<Tables<S>>,
+}
+
+struct Tables<S: BuildHasher> {
+    // Slot for index `i - 1`. `None` once the last `Symbol` referencing it
+    // was dropped and its storage was reclaimed.
+    entries: Vec<Option<NonNull<Header>>>,
+    // Maps string content back to its entry, for deduplication. Only
+    // contains entries for slots that are still alive.
+    indices: HashMap<&'static str, NonZeroU32, S>,
+}
+
+// A single heap allocation backing one interned value: `Header` immediately
+// followed by its `len` UTF-8 bytes, so looking up the string and bumping
+// its ref count touch a single cache-friendly block instead of chasing an
+// extra pointer into a separately-allocated `Box<str>`.
+struct Header {
+    ref_count: AtomicUsize,
+    len: usize,
+}
+
+fn header_layout(len: usize) -> (Layout, usize) {
+    let (layout, offset) = Layout::new::<Header>()
+        .extend(Layout::array::<u8>(len).unwrap())
+        .unwrap();
+    (layout.pad_to_align(), offset)
+}
+
+// SAFETY: `s` is copied into a fresh allocation sized to hold exactly one
+// `Header` and `s.len()` bytes; the returned pointer is non-null because a
+// failed allocation aborts via `handle_alloc_error`.
+unsafe fn alloc_entry(s: &str) -> NonNull<Header> {
+    let len = s.len();
+    let (layout, offset) = header_layout(len);
+    let ptr = alloc::alloc(layout);
+    if ptr.is_null() {
+        alloc::handle_alloc_error(layout);
+    }
+    (ptr as *mut Header).write(Header {
+        ref_count: AtomicUsize::new(1),
+        len,
+    });
+    std::ptr::copy_nonoverlapping(s.as_ptr(), ptr.add(offset), len);
+    NonNull::new_unchecked(ptr as *mut Header)
+}
+
+// SAFETY: `header` must point at a live allocation produced by
+// `alloc_entry` that has not yet been passed to `dealloc_entry`. The
+// returned `&str`'s lifetime is chosen by the caller, who must ensure the
+// allocation outlives it (see callers for the argument in each case).
+unsafe fn entry_str<'a>(header: NonNull<Header>) -> &'a str {
+    let len = header.as_ref().len;
+    let (_, offset) = header_layout(len);
+    let data = (header.as_ptr() as *const u8).add(offset);
+    std::str::from_utf8_unchecked(std::slice::from_raw_parts(data, len))
+}
+
+// SAFETY: `header` must point at a live allocation produced by
+// `alloc_entry`, and must not be used again afterwards.
+unsafe fn dealloc_entry(header: NonNull<Header>) {
+    let (layout, _) = header_layout(header.as_ref().len);
+    alloc::dealloc(header.as_ptr() as *mut u8, layout);
+}
+
+// SAFETY: a `Header` is only ever mutated through its atomic `ref_count`
+// (incremented by `Symbol::clone`/`Interner::intern`, decremented by
+// `Symbol::drop`) and is freed by at most one thread: the one that, while
+// holding `Interner::tables`'s lock, observes the count at zero. It is
+// therefore safe to share `Header` pointers -- and the `Tables`/`Symbol`s
+// built on them -- across threads.
+unsafe impl<S: BuildHasher + Send> Send for Tables<S> {}
+
+impl<S: BuildHasher + Default> Interner<S> {
+    /// Creates a new, empty interner.
+    pub fn new() -> Interner<S> {
+        Interner {
+            tables: Mutex::new(Tables {
+                entries: Vec::new(),
+                indices: HashMap::default(),
+            }),
+        }
+    }
+}
+
+impl<S: BuildHasher> Interner<S> {
+    /// Inserts `s` in the pool if it is not already there and returns a
+    /// symbol pointing to this new value or the existing one.
+    ///
+    /// The returned symbol cannot outlive `self`.
+    pub fn intern(&self, s: &str) -> Symbol<'_, S> {
+        let mut tables = self.tables.lock().unwrap();
+        self.intern_locked(&mut tables, s)
+    }
+
+    /// Interns every string in `strs`, in order, as a single critical
+    /// section: the whole batch runs under one held lock, rather than one
+    /// `lock()`/`unlock()` per string as repeated calls to [`Interner::intern`]
+    /// would. If none of `strs` is already present, the returned symbols are
+    /// therefore guaranteed to occupy a contiguous block of indices, since no
+    /// other thread's `intern`/`intern_all` call can be interleaved into the
+    /// middle of it. This is what [`symbols!`](crate::symbols) relies on to
+    /// make `is_keyword`'s range check safe.
+    pub fn intern_all(&self, strs: &[&str]) -> Vec<Symbol<'_, S>> {
+        let mut tables = self.tables.lock().unwrap();
+        strs.iter().map(|s| self.intern_locked(&mut tables, s)).collect()
+    }
+
+    // Shared by `intern` and `intern_all`: looks `s` up in (and, if
+    // necessary, inserts it into) `tables`, which the caller must already
+    // hold locked.
+    fn intern_locked(&self, tables: &mut Tables<S>, s: &str) -> Symbol<'_, S> {
+        if let Some(&index) = tables.indices.get(s) {
+            let header = tables.entries[slot_of(index)].unwrap();
+            // SAFETY: `header` is live: it is still referenced from
+            // `tables.indices`, and removal from there only ever happens
+            // together with freeing, both under this same lock.
+            unsafe { header.as_ref().ref_count.fetch_add(1, Ordering::Relaxed) };
+            return Symbol::new_in(self, index, header);
+        }
+        // SAFETY: `s` is a valid `&str`.
+        let header = unsafe { alloc_entry(s) };
+        // SAFETY: `header` was just allocated and is kept alive for as long
+        // as it stays reachable from `tables`, which outlives this borrow.
+        let key: &'static str = unsafe { entry_str(header) };
+        tables.entries.push(Some(header));
+        let index = NonZeroU32::new(tables.entries.len() as u32).unwrap();
+        tables.indices.insert(key, index);
+        Symbol::new_in(self, index, header)
+    }
+
+    /// Resolves `index` (as returned by [`Symbol::index`]) back to the
+    /// interned string, or `None` if `index` was not produced by this
+    /// interner or its value has since been reclaimed.
+    pub fn resolve(&self, index: u32) -> Option<&str> {
+        let tables = self.tables.lock().unwrap();
+        let header = (*tables.entries.get(index.checked_sub(1)? as usize)?)?;
+        // SAFETY: `header` is alive, see `intern_locked`.
+        Some(unsafe { entry_str(header) })
+    }
+
+    // If the ref count of the value at `index` is zero, removes it from the
+    // dedup map and frees its storage. Called when a `Symbol` is dropped,
+    // after that `Symbol` has already performed the (lock-free) atomic
+    // decrement itself; this function does not decrement the count.
+    //
+    // The count is re-read here, under `tables`'s lock, rather than trusted
+    // from the caller's earlier decrement: a concurrent `intern` of the
+    // same value also runs under this lock and would have already bumped
+    // the count back up before we can observe it, avoiding a race where we
+    // would free a symbol that `intern` just resurrected.
+    fn release(&self, index: NonZeroU32) {
+        let mut tables = self.tables.lock().unwrap();
+        let slot = slot_of(index);
+        let Some(header) = tables.entries[slot] else {
+            return;
+        };
+        // SAFETY: `header` is alive, see `intern_locked`.
+        if unsafe { header.as_ref().ref_count.load(Ordering::Acquire) } != 0 {
+            return;
+        }
+        tables.entries[slot] = None;
+        // SAFETY: `header` is alive up to this point, see `intern_locked`.
+        let key: &'static str = unsafe { entry_str(header) };
+        tables.indices.remove(key);
+        // SAFETY: `header` is no longer reachable from `tables`, so this is
+        // the only thread that will ever touch it again.
+        unsafe { dealloc_entry(header) };
+    }
+}
+
+impl<S: BuildHasher + Default> Default for Interner<S> {
+    fn default() -> Interner<S> {
+        Interner::new()
+    }
+}
+
+fn slot_of(index: NonZeroU32) -> usize {
+    (index.get() - 1) as usize
+}
+
+lazy_static! {
+    // The process-wide pool backing `Symbol::new`.
+    static ref DEFAULT_INTERNER: Interner = Interner::new();
+}
+
+/// A reference-counted handle to a value interned in an [`Interner`]'s
+/// string pool, represented as a small index into that pool.
+///
+/// `'i` is the lifetime of the [`Interner`] that produced this symbol (or
+/// `'static` for symbols created through [`Symbol::new`], which are backed
+/// by a process-wide interner). Cloning a `Symbol` increments the interned
+/// value's reference count; dropping one decrements it and, once it reaches
+/// zero, the interner reclaims the value's storage. `Symbol` is therefore
+/// `Clone` but not `Copy`.
+///
+/// Two symbols compare equal, hash the same, and order the same whenever
+/// they were produced by the same interner and carry the same index; unlike
+/// a pointer-based representation, this is deterministic across runs.
 ///
 /// See crate-level documentation for example and details.
-#[derive(Debug, Clone, Copy)]
-pub struct Symbol {
-    inner: &'static str,
+///
+/// `S` mirrors the owning [`Interner`]'s hasher parameter; it has no
+/// bearing on `Symbol` itself and is only there so `Symbol<'i, S>` can
+/// refer back to its `Interner<S>`.
+pub struct Symbol<'i, S: BuildHasher = FxBuildHasher> {
+    index: NonZeroU32,
+    header: NonNull<Header>,
+    interner: &'i Interner<S>,
 }
 
-impl Symbol {
-    /// Inserts in the pool the value `s` if it is no already there and returns
-    /// a symbol pointing to this new value or the existing one.
-    pub fn new<R: AsRef<str>>(s: R) -> Symbol {
+// SAFETY: see the justification on `Tables`; a `Symbol` only ever touches
+// its `Header` through the atomic `ref_count` (clone/drop) or by reading
+// `len` and the trailing bytes, which are written once at allocation and
+// never change afterwards.
+unsafe impl<'i, S: BuildHasher + Send> Send for Symbol<'i, S> {}
+unsafe impl<'i, S: BuildHasher + Sync> Sync for Symbol<'i, S> {}
+
+impl<'i, S: BuildHasher> Symbol<'i, S> {
+    fn new_in(interner: &'i Interner<S>, index: NonZeroU32, header: NonNull<Header>) -> Symbol<'i, S> {
         Symbol {
-            inner: intern(s.as_ref()),
+            index,
+            header,
+            interner,
         }
     }
 
+    /// Returns the index of this symbol within its interner's string table.
+    pub fn index(&self) -> u32 {
+        self.index.get()
+    }
+
     /// Returns a reference to the string pointed to by this symbol.
-    pub fn as_str(&self) -> &'static str {
-        self.inner
+    pub fn as_str(&self) -> &'i str {
+        // SAFETY: `self.header` stays alive for as long as this `Symbol`
+        // (which holds one of its ref counts) is alive.
+        unsafe { entry_str(self.header) }
+    }
+}
+
+impl Symbol<'static> {
+    /// Inserts in the process-wide pool the value `s` if it is not already
+    /// there and returns a symbol pointing to this new value or the existing
+    /// one.
+    ///
+    /// This is a convenience wrapper over a lazily-initialized default
+    /// [`Interner`]. Code that wants finer control over when interned
+    /// memory is released should create its own `Interner` and call
+    /// [`Interner::intern`] instead.
+    pub fn new<R: AsRef<str>>(s: R) -> Symbol<'static> {
+        DEFAULT_INTERNER.intern(s.as_ref())
+    }
+
+    /// Interns every string in `strs` against the process-wide pool as a
+    /// single atomic batch: see [`Interner::intern_all`].
+    pub fn new_all(strs: &[&str]) -> Vec<Symbol<'static>> {
+        DEFAULT_INTERNER.intern_all(strs)
     }
 }
 
-impl From<&str> for Symbol {
-    /// Generates a symbol for `source`.
+impl From<&str> for Symbol<'static> {
+    /// Generates a symbol for `source` in the process-wide pool.
     fn from(source: &str) -> Self {
         Symbol::new(source)
     }
 }
 
-impl PartialEq for Symbol {
-    fn eq(&self, other: &Symbol) -> bool {
-        self.inner.as_ptr() == other.inner.as_ptr()
+impl<'i, S: BuildHasher> Clone for Symbol<'i, S> {
+    fn clone(&self) -> Symbol<'i, S> {
+        // SAFETY: `self.header` is alive, see `Symbol::as_str`.
+        unsafe { self.header.as_ref().ref_count.fetch_add(1, Ordering::Relaxed) };
+        Symbol {
+            index: self.index,
+            header: self.header,
+            interner: self.interner,
+        }
+    }
+}
+
+impl<'i, S: BuildHasher> Drop for Symbol<'i, S> {
+    fn drop(&mut self) {
+        // Fast, lock-free decrement; only take the interner's lock (via
+        // `release`) on the path that might actually free something.
+        // SAFETY: `self.header` is alive, see `Symbol::as_str`.
+        let previous = unsafe { self.header.as_ref().ref_count.fetch_sub(1, Ordering::AcqRel) };
+        if previous == 1 {
+            self.interner.release(self.index);
+        }
+    }
+}
+
+impl<'i, S: BuildHasher> fmt::Debug for Symbol<'i, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Symbol").field("index", &self.index).finish()
     }
 }
 
-impl Eq for Symbol {}
+impl<'i, S: BuildHasher> PartialEq for Symbol<'i, S> {
+    fn eq(&self, other: &Symbol<'i, S>) -> bool {
+        std::ptr::eq(self.interner, other.interner) && self.index == other.index
+    }
+}
+
+impl<'i, S: BuildHasher> Eq for Symbol<'i, S> {}
+
+impl<'i, S: BuildHasher> PartialOrd for Symbol<'i, S> {
+    fn partial_cmp(&self, other: &Symbol<'i, S>) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'i, S: BuildHasher> Ord for Symbol<'i, S> {
+    fn cmp(&self, other: &Symbol<'i, S>) -> std::cmp::Ordering {
+        self.index.cmp(&other.index)
+    }
+}
+
+impl<'i, S: BuildHasher> Hash for Symbol<'i, S> {
+    /// Returns a hash of the index wrapped by this symbol (rather than the
+    /// pointed-to string content), so it is deterministic across runs.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+    }
+}
 
 // Implement mixed comparisons.
 // Code lifted from String implementation.
 // I do not understand why &'a str versions are required nor how they work.
 // I naively thought str versions would be sufficient.
 
-impl PartialEq<str> for Symbol {
+impl<'i, S: BuildHasher> PartialEq<str> for Symbol<'i, S> {
     fn eq(&self, other: &str) -> bool {
-        self.inner[..] == other[..]
+        self.as_str() == other
     }
 }
 
-impl PartialEq<Symbol> for str {
-    fn eq(&self, other: &Symbol) -> bool {
-        self[..] == other.inner[..]
+impl<'i, S: BuildHasher> PartialEq<Symbol<'i, S>> for str {
+    fn eq(&self, other: &Symbol<'i, S>) -> bool {
+        self == other.as_str()
     }
 }
 
-impl<'a> PartialEq<Symbol> for &'a str {
-    fn eq(&self, other: &Symbol) -> bool {
-        self[..] == other.inner[..]
+impl<'a, 'i, S: BuildHasher> PartialEq<Symbol<'i, S>> for &'a str {
+    fn eq(&self, other: &Symbol<'i, S>) -> bool {
+        *self == other.as_str()
     }
 }
 
-impl<'a> PartialEq<&'a str> for Symbol {
+impl<'a, 'i, S: BuildHasher> PartialEq<&'a str> for Symbol<'i, S> {
     fn eq(&self, other: &&'a str) -> bool {
-        self.inner[..] == other[..]
+        self.as_str() == *other
     }
 }
 
-impl Hash for Symbol {
-    /// Returns a hash of the pointer wrapped by this symbol (rather than the
-    /// pointed-to string content).
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        self.inner.as_ptr().hash(state);
+/// A pool of interned values of an arbitrary `Eq + Hash` type `T`.
+///
+/// This generalizes the idea behind [`Interner`]/[`Symbol`] (deduplicate
+/// equal values, then compare the survivors by address instead of by
+/// structural equality) to types other than `str` -- slices, tuples, or
+/// user-defined AST nodes, for instance, where structural comparison is
+/// expensive but pointer comparison after interning is cheap.
+///
+/// Unlike `Symbol`, an individual [`Interned`] value is not reference
+/// counted: its storage is reclaimed only when the whole `TypeInterner` is
+/// dropped.
+pub struct TypeInterner<T: Eq + Hash + 'static> {
+    values: Mutex<HashSet<Box<T>>>,
+}
+
+impl<T: Eq + Hash + 'static> TypeInterner<T> {
+    /// Creates a new, empty interner.
+    pub fn new() -> TypeInterner<T> {
+        TypeInterner {
+            values: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Inserts `value` in the pool if an equal value is not already there
+    /// and returns a handle to this value or the existing one.
+    ///
+    /// The returned handle cannot outlive `self`.
+    pub fn intern(&self, value: T) -> Interned<'_, T> {
+        let mut values = self.values.lock().unwrap();
+        if let Some(existing) = values.get(&value) {
+            // SAFETY: see `Interner::intern_locked`; the same stability argument
+            // applies to `HashSet<Box<T>>`.
+            let ptr: &'static T = unsafe { std::mem::transmute::<&T, &T>(existing) };
+            return Interned::new_in(ptr);
+        }
+        let boxed = Box::new(value);
+        // SAFETY: see above.
+        let ptr: &'static T = unsafe { std::mem::transmute::<&T, &T>(&boxed) };
+        values.insert(boxed);
+        Interned::new_in(ptr)
     }
 }
 
-lazy_static! {
-    // All strings interned so far.
-    static ref STRINGS : Mutex<HashSet<&'static str>> = {
-        Mutex::new(HashSet::new())
-    };
+impl<T: Eq + Hash + 'static> Default for TypeInterner<T> {
+    fn default() -> TypeInterner<T> {
+        TypeInterner::new()
+    }
 }
 
-// Returns a reference to a string that has the same value as `s` and is guaranteed to be unique.
-fn intern(str: &str) -> &'static str {
-    let mut g = STRINGS.lock().unwrap();
-    // TODO: Use HashSet::get_or_insert() when stabilized
-    match g.get(str) {
-        Some(s) => s,
-        None => {
-            let b = Box::new(str.to_string());
-            let s = Box::leak(b).as_str();
-            g.insert(s);
-            s
+/// A handle to a value interned in a [`TypeInterner`].
+///
+/// `'i` is the lifetime of the `TypeInterner` that produced this handle.
+/// Two handles compare equal, and hash the same, if and only if they were
+/// produced by interning equal values in the *same* interner: comparison is
+/// by address, in `O(1)`, rather than by (potentially expensive) structural
+/// equality on `T`.
+pub struct Interned<'i, T: 'static> {
+    ptr: &'static T,
+    _pool: std::marker::PhantomData<&'i T>,
+}
+
+impl<'i, T: 'static> Interned<'i, T> {
+    fn new_in(ptr: &'static T) -> Interned<'i, T> {
+        Interned {
+            ptr,
+            _pool: std::marker::PhantomData,
         }
     }
+
+    /// Returns a reference to the interned value.
+    pub fn get(&self) -> &'i T {
+        self.ptr
+    }
+}
+
+impl<'i, T: 'static> Clone for Interned<'i, T> {
+    fn clone(&self) -> Interned<'i, T> {
+        *self
+    }
+}
+
+impl<'i, T: 'static> Copy for Interned<'i, T> {}
+
+impl<'i, T: 'static> PartialEq for Interned<'i, T> {
+    fn eq(&self, other: &Interned<'i, T>) -> bool {
+        std::ptr::eq(self.ptr, other.ptr)
+    }
+}
+
+impl<'i, T: 'static> Eq for Interned<'i, T> {}
+
+impl<'i, T: fmt::Debug + 'static> fmt::Debug for Interned<'i, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.ptr, f)
+    }
+}
+
+impl<'i, T: 'static> Hash for Interned<'i, T> {
+    /// Returns a hash of the pointer wrapped by this handle (rather than the
+    /// pointed-to value), matching the address-based `Eq` above.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (self.ptr as *const T).hash(state);
+    }
+}
+
+/// Declares a table of well-known symbols, as rustc's `libsyntax_pos` does
+/// for language keywords.
+///
+/// `symbols! { As: "as", Fn: "fn", .. }` expands to a `kw` module holding:
+/// - one accessor function per entry (`kw::As() -> Symbol<'static>`);
+/// - `kw::is_keyword(sym)`, a cheap check for whether `sym` is one of them.
+///
+/// All entries are interned together, under a single held lock (see
+/// [`Interner::intern_all`]), the first time any one of them is accessed --
+/// so, as long as none of them has already been interned individually
+/// beforehand, they are guaranteed to land on a contiguous block of indices
+/// and `is_keyword` can recognize the whole table with one range check on
+/// [`Symbol::index`] instead of a string compare per entry.
+///
+/// This guarantee is per-table, not process-wide: call `kw::init()` (or any
+/// accessor) for a given table before any unrelated code can call
+/// [`Symbol::new`] with one of that table's strings, and do not intern two
+/// `symbols!` tables concurrently with each other -- either could otherwise
+/// land a foreign index inside this table's range, or split this table's
+/// entries around a foreign one, before the first access locks them in.
+///
+/// # Examples
+///
+/// ```
+/// use stagiaire::symbols;
+///
+/// symbols! {
+///     As: "as",
+///     Fn: "fn",
+/// }
+///
+/// assert_eq!(kw::As().as_str(), "as");
+/// assert!(kw::is_keyword(&kw::Fn()));
+/// assert!(!kw::is_keyword(&stagiaire::Symbol::new("not_a_keyword")));
+/// ```
+#[macro_export]
+macro_rules! symbols {
+    ( $( $name:ident : $text:literal ),* $(,)? ) => {
+        #[allow(non_snake_case)]
+        pub mod kw {
+            //! Predefined symbols generated by [`symbols!`](macro.symbols.html).
+
+            use $crate::Symbol;
+
+            $crate::__lazy_static::lazy_static! {
+                // Interned as one batch, under one held lock, so no other
+                // thread's `Symbol::new` call can land an index in the
+                // middle of this table -- see `symbols!`'s doc comment for
+                // the precondition this still relies on.
+                static ref KEYWORDS: ::std::vec::Vec<Symbol<'static>> =
+                    Symbol::new_all(&[ $( $text ),* ]);
+            }
+
+            /// Forces the whole table to be interned now, rather than on
+            /// first access of an individual symbol below.
+            pub fn init() {
+                $crate::__lazy_static::initialize(&KEYWORDS);
+            }
+
+            /// Returns `true` if `sym` falls within this table's (contiguous)
+            /// block of indices.
+            pub fn is_keyword(sym: &Symbol<'static>) -> bool {
+                let first = KEYWORDS[0].index();
+                let last = KEYWORDS[KEYWORDS.len() - 1].index();
+                (first..=last).contains(&sym.index())
+            }
+
+            $crate::symbols!(@accessors 0; $( $name )*);
+        }
+    };
+    (@accessors $i:expr; $name:ident $( $rest:ident )*) => {
+        pub fn $name() -> Symbol<'static> {
+            KEYWORDS[$i].clone()
+        }
+        $crate::symbols!(@accessors $i + 1; $( $rest )*);
+    };
+    (@accessors $i:expr; ) => {};
 }
@@ -1,6 +1,13 @@
+use std::collections::hash_map::RandomState;
 use std::hash::{Hash, Hasher};
 
-use stagiaire::Symbol;
+use stagiaire::{symbols, Interned, Interner, Symbol, TypeInterner};
+
+symbols! {
+    As: "as",
+    Break: "break",
+    Fn: "fn",
+}
 
 #[test]
 fn interned_string_has_same_value() {
@@ -23,14 +30,41 @@ fn different_strings_have_different_addresses() {
 }
 
 #[test]
-fn a_copy_is_shallow() {
+fn a_clone_shares_the_same_value() {
     let sym1 = Symbol::new("foo");
-    let sym2 = sym1;
+    let sym2 = sym1.clone();
     assert_eq!(sym1.as_str().as_ptr(), sym2.as_str().as_ptr());
 }
 
 #[test]
-fn hash_computed_on_string_address_not_value() {
+fn dropping_the_last_clone_reclaims_the_value() {
+    let interner: Interner = Interner::new();
+    let index = {
+        let sym1 = interner.intern("foo");
+        let sym2 = sym1.clone();
+        drop(sym1);
+        // `sym2` still keeps the value alive.
+        assert_eq!(interner.resolve(sym2.index()), Some("foo"));
+        sym2.index()
+    };
+    assert_eq!(interner.resolve(index), None);
+}
+
+#[test]
+fn reinterning_after_a_drop_gets_a_fresh_slot() {
+    let interner: Interner = Interner::new();
+    let first = interner.intern("foo");
+    let first_index = first.index();
+    drop(first);
+    // `first`'s storage has been reclaimed, so re-interning "foo" cannot
+    // reuse its slot: it must get a new, distinct index.
+    let second = interner.intern("foo");
+    assert_ne!(second.index(), first_index);
+    assert_eq!(interner.resolve(second.index()), Some("foo"));
+}
+
+#[test]
+fn hash_computed_on_index_not_value() {
     let sym = Symbol::new("zorglub");
 
     use std::collections::hash_map;
@@ -40,11 +74,26 @@ fn hash_computed_on_string_address_not_value() {
     let mut hasher_str = hash_map::DefaultHasher::new();
     "zorglub".hash(&mut hasher_str);
 
-    // This could fail because the string address and content could hash to the same value but
+    // This could fail because the index and the string content could hash to the same value but
     // this seems unlikely.
     assert_ne!(hasher_sym.finish(), hasher_str.finish());
 }
 
+#[test]
+fn hash_is_deterministic_across_equal_symbols() {
+    use std::collections::hash_map;
+
+    let sym1 = Symbol::new("zorglub");
+    let sym2 = Symbol::new("zorglub");
+
+    let mut hasher1 = hash_map::DefaultHasher::new();
+    sym1.hash(&mut hasher1);
+    let mut hasher2 = hash_map::DefaultHasher::new();
+    sym2.hash(&mut hasher2);
+
+    assert_eq!(hasher1.finish(), hasher2.finish());
+}
+
 #[test]
 fn compare_with_str_ref() {
     assert_eq!("foo", Symbol::new("foo"));
@@ -56,13 +105,13 @@ fn compare_with_str_ref() {
 #[test]
 fn symbol_is_send() {
     fn assert_send<T: Send>() {}
-    assert_send::<Symbol>();
+    assert_send::<Symbol<'static>>();
 }
 
 #[test]
 fn symbol_is_sync() {
     fn assert_sync<T: Sync>() {}
-    assert_sync::<Symbol>();
+    assert_sync::<Symbol<'static>>();
 }
 
 #[test]
@@ -84,4 +133,88 @@ fn serialize() {
 fn deserialize() {
     let sym = serde_json::from_str::<Symbol>(r#""zorglub""#).unwrap();
     assert_eq!(sym.as_str(), "zorglub")
+}
+
+#[test]
+fn scoped_interner_dedups_like_the_default_one() {
+    let interner: Interner = Interner::new();
+    let sym1 = interner.intern("foo");
+    let sym2 = interner.intern("foo");
+    assert_eq!(sym1, sym2);
+    assert_eq!(sym1.index(), sym2.index());
+}
+
+#[test]
+fn scoped_interners_are_independent() {
+    let interner1: Interner = Interner::new();
+    let interner2: Interner = Interner::new();
+    let sym1 = interner1.intern("foo");
+    let sym2 = interner2.intern("bar");
+    let _ = interner2.intern("foo");
+    assert_eq!(interner1.resolve(sym1.index()), Some("foo"));
+    assert_eq!(interner2.resolve(sym2.index()), Some("bar"));
+    // `sym1`'s index is meaningless in `interner2`'s table.
+    assert_ne!(interner2.resolve(sym1.index()), Some("foo"));
+}
+
+#[test]
+fn interner_works_with_a_non_default_hasher() {
+    let interner: Interner<RandomState> = Interner::new();
+    let sym1 = interner.intern("foo");
+    let sym2 = interner.intern("foo");
+    assert_eq!(sym1, sym2);
+    assert_eq!(interner.resolve(sym1.index()), Some("foo"));
+}
+
+#[test]
+fn resolve_returns_none_for_unknown_index() {
+    let interner: Interner = Interner::new();
+    assert_eq!(interner.resolve(1), None);
+    interner.intern("foo");
+    assert_eq!(interner.resolve(42), None);
+}
+
+#[test]
+fn equal_values_interned_in_a_type_interner_are_equal() {
+    let interner: TypeInterner<Vec<i32>> = TypeInterner::new();
+    let a = interner.intern(vec![1, 2, 3]);
+    let b = interner.intern(vec![1, 2, 3]);
+    assert_eq!(a, b);
+    assert_eq!(std::ptr::eq(a.get(), b.get()), true);
+}
+
+#[test]
+fn unequal_values_interned_in_a_type_interner_are_not_equal() {
+    let interner: TypeInterner<Vec<i32>> = TypeInterner::new();
+    let a = interner.intern(vec![1, 2, 3]);
+    let b = interner.intern(vec![4, 5, 6]);
+    assert_ne!(a, b);
+}
+
+#[test]
+fn interned_handle_gives_back_the_value() {
+    let interner: TypeInterner<(i32, &'static str)> = TypeInterner::new();
+    let handle: Interned<'_, (i32, &'static str)> = interner.intern((42, "foo"));
+    assert_eq!(*handle.get(), (42, "foo"));
+}
+
+#[test]
+fn symbols_macro_generates_working_accessors() {
+    assert_eq!(kw::As().as_str(), "as");
+    assert_eq!(kw::Break().as_str(), "break");
+    assert_eq!(kw::Fn().as_str(), "fn");
+}
+
+#[test]
+fn symbols_macro_dedups_with_the_default_pool() {
+    assert_eq!(kw::As(), Symbol::new("as"));
+}
+
+#[test]
+fn is_keyword_recognizes_only_the_declared_table() {
+    kw::init();
+    assert!(kw::is_keyword(&kw::As()));
+    assert!(kw::is_keyword(&kw::Break()));
+    assert!(kw::is_keyword(&kw::Fn()));
+    assert!(!kw::is_keyword(&Symbol::new("definitely_not_a_keyword")));
 }
\ No newline at end of file